@@ -0,0 +1,136 @@
+use core::convert::Infallible;
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle, Pixel};
+use embedded_hal::digital::v2::OutputPin;
+use st7789::Error;
+
+/// An in-RAM copy of a single horizontal band of the display, `BAND_HEIGHT` pixels tall and the
+/// full screen `WIDTH` wide.
+///
+/// A full `WIDTH`x`HEIGHT` copy of the screen doesn't fit in the ~64 KB of SRAM on the STM32F303
+/// this targets, so instead [`Game::render`](crate::game::Game::render) is called once per band
+/// (see [`FrameBuffer::BANDS`]) with [`FrameBuffer::select_band`] choosing which one is currently
+/// live; pixels drawn outside the selected band are simply dropped; they're for a different pass.
+/// [`FrameBuffer::flush`] then pushes only the pixels that changed within the selected band to
+/// the ST7789 as a single windowed SPI write, instead of one small transaction per snake/food
+/// cell every step.
+pub struct FrameBuffer<const WIDTH: usize, const HEIGHT: usize, const BAND_HEIGHT: usize>
+where
+    [(); WIDTH * BAND_HEIGHT]:,
+{
+    pixels: [u16; WIDTH * BAND_HEIGHT],
+    /// Index of the band currently selected, in units of `BAND_HEIGHT` rows from the top.
+    band: usize,
+    /// The smallest rectangle, in band-local coordinates, covering every pixel written into the
+    /// selected band since it was last flushed, if any.
+    dirty: Option<Rectangle>,
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const BAND_HEIGHT: usize> FrameBuffer<WIDTH, HEIGHT, BAND_HEIGHT>
+where
+    [(); WIDTH * BAND_HEIGHT]:,
+{
+    /// How many bands make up the full screen height. A full frame requires rendering and
+    /// flushing every band in turn.
+    pub const BANDS: usize = (HEIGHT + BAND_HEIGHT - 1) / BAND_HEIGHT;
+
+    pub fn new() -> Self {
+        FrameBuffer { pixels: [0; WIDTH * BAND_HEIGHT], band: 0, dirty: None }
+    }
+
+    /// Choose which band subsequent draws land in; draws outside it are dropped. Clears any
+    /// pending (unflushed) dirty region from the previously selected band, and blanks the
+    /// (reused) pixel storage to black: otherwise leftover pixels from whichever band last
+    /// occupied this storage would show through any gap in this band's dirty bounding rectangle
+    /// that this pass doesn't happen to redraw (the game always paints its background black, so
+    /// this is the correct content for an untouched cell, not just a safe default).
+    pub fn select_band(&mut self, band: usize) {
+        self.band = band;
+        self.dirty = None;
+        self.pixels = [0; WIDTH * BAND_HEIGHT];
+    }
+
+    fn mark_dirty(&mut self, local_point: Point) {
+        let touched = Rectangle::new(local_point, Size::new(1, 1));
+        self.dirty = Some(match self.dirty {
+            Some(existing) => Self::_union(existing, touched),
+            None => touched,
+        });
+    }
+
+    fn _union(a: Rectangle, b: Rectangle) -> Rectangle {
+        let min_x = a.top_left.x.min(b.top_left.x);
+        let min_y = a.top_left.y.min(b.top_left.y);
+        let max_x = (a.top_left.x + a.size.width as i32).max(b.top_left.x + b.size.width as i32);
+        let max_y = (a.top_left.y + a.size.height as i32).max(b.top_left.y + b.size.height as i32);
+        Rectangle::new(Point::new(min_x, min_y), Size::new((max_x - min_x) as u32, (max_y - min_y) as u32))
+    }
+
+    /// Push any pixels changed since the last flush, within the selected band, to `display` as a
+    /// single windowed write. Does nothing, and issues no SPI transaction, if nothing changed.
+    pub fn flush<DI, RST, BL>(&mut self, display: &mut st7789::ST7789<DI, RST, BL>) -> Result<(), Error<Infallible>>
+    where
+        DI: WriteOnlyDataCommand,
+        RST: OutputPin<Error = Infallible>,
+        BL: OutputPin<Error = Infallible>,
+    {
+        let Some(rect) = self.dirty.take() else {
+            return Ok(());
+        };
+
+        let band_top = (self.band * BAND_HEIGHT) as i32;
+        let sx = rect.top_left.x as u16;
+        let sy = (band_top + rect.top_left.y) as u16;
+        let ex = (rect.top_left.x + rect.size.width as i32 - 1) as u16;
+        let ey = (band_top + rect.top_left.y + rect.size.height as i32 - 1) as u16;
+
+        let width = WIDTH as i32;
+        let pixels = &self.pixels;
+        let y_range = rect.top_left.y..rect.top_left.y + rect.size.height as i32;
+        let x_range = rect.top_left.x..rect.top_left.x + rect.size.width as i32;
+        let colors = y_range.flat_map(move |y| x_range.clone().map(move |x| pixels[(y * width + x) as usize]));
+
+        display.set_pixels(sx, sy, ex, ey, colors)
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const BAND_HEIGHT: usize> OriginDimensions
+    for FrameBuffer<WIDTH, HEIGHT, BAND_HEIGHT>
+where
+    [(); WIDTH * BAND_HEIGHT]:,
+{
+    fn size(&self) -> Size {
+        // Report the full screen size, not the (much smaller) band buffer: callers draw the
+        // whole scene every pass and rely on out-of-band pixels simply being dropped below.
+        Size::new(WIDTH as u32, HEIGHT as u32)
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const BAND_HEIGHT: usize> DrawTarget
+    for FrameBuffer<WIDTH, HEIGHT, BAND_HEIGHT>
+where
+    [(); WIDTH * BAND_HEIGHT]:,
+{
+    type Color = Rgb565;
+    type Error = Error<Infallible>;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let band_top = (self.band * BAND_HEIGHT) as i32;
+        let band_bottom = band_top + BAND_HEIGHT as i32;
+
+        for Pixel(point, colour) in pixels {
+            if point.x < 0 || point.x as u32 >= WIDTH as u32 || point.y < band_top || point.y >= band_bottom {
+                // Either off-screen, or destined for a different band's pass.
+                continue;
+            }
+            let local_point = Point::new(point.x, point.y - band_top);
+            let index = local_point.y as usize * WIDTH + local_point.x as usize;
+            self.pixels[index] = colour.into_storage();
+            self.mark_dirty(local_point);
+        }
+        Ok(())
+    }
+}
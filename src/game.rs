@@ -1,14 +1,18 @@
-use core::{convert::Infallible, iter::Cycle, slice::Iter};
+use core::{cell::Cell, convert::Infallible, fmt::Write as _};
 use embedded_graphics::{
+    image::Image,
+    mono_font::{ascii::FONT_6X10, MonoTextStyle},
     pixelcolor::Rgb565,
-    prelude::{DrawTarget, RgbColor, Size},
+    prelude::{Drawable, DrawTarget, OriginDimensions, RgbColor, Size},
     primitives::Rectangle,
+    text::Text,
 };
 use hash32::{Hash, Hasher};
 use heapless::{self, FnvIndexMap};
 use st7789::Error;
+use tinybmp::Bmp;
 
-use crate::inputs::{Direction, GameInputs};
+use crate::inputs::Direction;
 
 /// A position on the screen. (0, 0) is the top-left of the screen.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -62,6 +66,15 @@ impl Into<Vector> for Direction {
     }
 }
 
+/// The top row of the screen is reserved for the score HUD (see [`Game::render`]); the playfield
+/// is drawn starting one `PIXEL_WIDTH` row below it.
+fn _playfield_top_left<const PIXEL_WIDTH: u8>(point: &Point) -> embedded_graphics::prelude::Point {
+    embedded_graphics::prelude::Point {
+        x: point.x as i32 * PIXEL_WIDTH as i32,
+        y: point.y as i32 * PIXEL_WIDTH as i32 + PIXEL_WIDTH as i32,
+    }
+}
+
 /// Render a logical game position as pixels on the screen, with upscaling.
 fn _render_point<const PIXEL_WIDTH: u8, R>(point: &Point, colour: Rgb565, target: &mut R)
 where
@@ -71,13 +84,73 @@ where
         width: PIXEL_WIDTH as u32,
         height: PIXEL_WIDTH as u32,
     };
-    let top_left = embedded_graphics::prelude::Point {
-        x: point.x as i32 * PIXEL_WIDTH as i32,
-        y: point.y as i32 * PIXEL_WIDTH as i32,
-    };
+    let top_left = _playfield_top_left::<PIXEL_WIDTH>(point);
     target.fill_solid(&Rectangle { top_left, size }, colour).unwrap();
 }
 
+/// Blit a sprite at a logical game position. The sprite is drawn at its native resolution with no
+/// scaling, so it must already be authored as a `PIXEL_WIDTH` x `PIXEL_WIDTH` asset or it'll
+/// misalign with (or overlap) neighbouring cells.
+fn _render_sprite<const PIXEL_WIDTH: u8, R>(point: &Point, sprite: &Bmp<'static, Rgb565>, target: &mut R)
+where
+    R: DrawTarget<Color = Rgb565, Error = Error<Infallible>>,
+{
+    // A plain assert, not debug_assert: this is firmware's load-bearing guard against a
+    // mis-sized asset silently misaligning sprites, and release builds are how this ships.
+    assert_eq!(sprite.size(), Size::new(PIXEL_WIDTH as u32, PIXEL_WIDTH as u32));
+    let top_left = _playfield_top_left::<PIXEL_WIDTH>(point);
+    Image::new(sprite, top_left).draw(target).unwrap();
+}
+
+/// The sprite assets used to render the game, parsed once at startup rather than on every frame.
+struct Sprites {
+    body: Bmp<'static, Rgb565>,
+    head_up: Bmp<'static, Rgb565>,
+    head_down: Bmp<'static, Rgb565>,
+    head_left: Bmp<'static, Rgb565>,
+    head_right: Bmp<'static, Rgb565>,
+    /// One food sprite per level tier, cycled the same way the old flashing colours were.
+    food_by_level: [Bmp<'static, Rgb565>; 4],
+}
+impl Sprites {
+    fn new() -> Self {
+        Sprites {
+            body: Bmp::from_slice(include_bytes!("../assets/body.bmp")).unwrap(),
+            head_up: Bmp::from_slice(include_bytes!("../assets/head_up.bmp")).unwrap(),
+            head_down: Bmp::from_slice(include_bytes!("../assets/head_down.bmp")).unwrap(),
+            head_left: Bmp::from_slice(include_bytes!("../assets/head_left.bmp")).unwrap(),
+            head_right: Bmp::from_slice(include_bytes!("../assets/head_right.bmp")).unwrap(),
+            food_by_level: [
+                Bmp::from_slice(include_bytes!("../assets/food_level0.bmp")).unwrap(),
+                Bmp::from_slice(include_bytes!("../assets/food_level1.bmp")).unwrap(),
+                Bmp::from_slice(include_bytes!("../assets/food_level2.bmp")).unwrap(),
+                Bmp::from_slice(include_bytes!("../assets/food_level3.bmp")).unwrap(),
+            ],
+        }
+    }
+
+    fn head(&self, direction: Direction) -> &Bmp<'static, Rgb565> {
+        match direction {
+            Direction::Up => &self.head_up,
+            Direction::Down => &self.head_down,
+            Direction::Left => &self.head_left,
+            Direction::Right => &self.head_right,
+        }
+    }
+
+    fn food(&self, level: u32) -> &Bmp<'static, Rgb565> {
+        &self.food_by_level[level as usize % self.food_by_level.len()]
+    }
+}
+
+/// What happened as a result of a single `Snake::update` step.
+struct UpdateOutcome {
+    /// Whether the new head position collided with the snake's own body.
+    collided: bool,
+    /// Whether the new head landed on a food item.
+    ate_food: bool,
+}
+
 struct Snake<const GAME_WIDTH: u8, const GAME_HEIGHT: u8, const PIXEL_WIDTH: u8>
 where
     [(); GAME_WIDTH as usize * GAME_HEIGHT as usize]:,
@@ -92,8 +165,6 @@ impl<const GAME_WIDTH: u8, const GAME_HEIGHT: u8, const PIXEL_WIDTH: u8> Snake<G
 where
     [(); GAME_WIDTH as usize * GAME_HEIGHT as usize]:,
 {
-    const COLOUR: Rgb565 = Rgb565::GREEN;
-
     pub fn new(initial_point: Point, initial_direction: Direction) -> Self {
         let mut points = heapless::Deque::new();
         points.push_back(initial_point).unwrap();
@@ -104,15 +175,22 @@ where
         }
     }
 
-    /// Move the snake in the current direction.
-    pub fn update<const N: usize>(&mut self, food: &mut FnvIndexMap<Point, Food, N>) {
+    /// Move the snake in the current direction, reporting what happened as a result.
+    pub fn update<const N: usize>(&mut self, food: &mut FnvIndexMap<Point, Food, N>) -> UpdateOutcome {
         let Some(old_head) = self.points.front() else {
-            return
+            return UpdateOutcome { collided: false, ate_food: false }
         };
         let direction_delta: Vector = self.direction.into();
         let new_head = Self::_add_with_wraparound(old_head.clone(), direction_delta);
 
         let ate_food = food.remove(&new_head).is_some();
+
+        // Moving onto the current tail cell is only fatal if we're growing this step (we ate
+        // food, so the tail stays put); otherwise the tail vacates that cell in the same step the
+        // head arrives, so it's a legal move (e.g. following the snake's own loop).
+        let vacating_tail = !ate_food && self.points.back() == Some(&new_head);
+        let collided = !vacating_tail && self.contains(&new_head);
+
         if !ate_food {
             // If we didn't eat a food, remove the last tail location to make up for the head moving. If we did eat food,
             // leave the tail point where it is so that we increase our length by 1.
@@ -120,6 +198,7 @@ where
         }
 
         self.points.push_front(new_head).unwrap();
+        UpdateOutcome { collided, ate_food }
     }
 
     pub fn set_direction(&mut self, direction: Direction) {
@@ -129,7 +208,7 @@ where
         self.direction
     }
 
-    fn render<R>(&self, target: &mut R)
+    fn render<R>(&self, sprites: &Sprites, target: &mut R)
     where
         R: DrawTarget<Color = Rgb565, Error = Error<Infallible>>,
     {
@@ -137,8 +216,12 @@ where
         if let Some(old_point) = self.old_tail {
             _render_point::<PIXEL_WIDTH, R>(&old_point, Rgb565::BLACK, target);
         }
-        for point in self.points.iter() {
-            _render_point::<PIXEL_WIDTH, R>(point, Self::COLOUR, target);
+        let mut points = self.points.iter();
+        if let Some(head) = points.next() {
+            _render_sprite::<PIXEL_WIDTH, R>(head, sprites.head(self.direction), target);
+        }
+        for point in points {
+            _render_sprite::<PIXEL_WIDTH, R>(point, &sprites.body, target);
         }
     }
 
@@ -159,93 +242,356 @@ where
             y: y.rem_euclid(GAME_HEIGHT as i16) as u8,
         }
     }
+
+    /// Whether `point` is currently occupied by any part of the snake.
+    fn contains(&self, point: &Point) -> bool {
+        self.points.iter().any(|p| p == point)
+    }
 }
 
-#[derive(Debug)]
-struct Food {
-    colours: Cycle<Iter<'static, Rgb565>>,
-    next_colour: Rgb565,
+/// A small `no_std` xorshift32 PRNG, used to pick random food locations without pulling in a
+/// full `rand` dependency.
+struct Rng {
+    state: u32,
 }
-impl Food {
-    const COLOURS: &'static [Rgb565] = &[Rgb565::WHITE, Rgb565::RED];
+impl Rng {
+    /// `seed` must be non-zero, or the generator will produce nothing but zeroes.
+    fn new(seed: u32) -> Self {
+        Rng { state: if seed == 0 { 1 } else { seed } }
+    }
 
-    fn new() -> Self {
-        let mut colours = Self::COLOURS.iter().cycle();
-        let next_colour = *colours.next().unwrap();
-        Food { colours, next_colour }
+    fn next_u32(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        x
     }
+}
 
-    fn update(&mut self) {
-        self.next_colour = *self.colours.next().unwrap();
+#[derive(Debug)]
+struct Food {
+    /// The level this food spawned at, which selects its sprite (see `Sprites::food`).
+    level: u32,
+}
+impl Food {
+    fn new(level: u32) -> Self {
+        Food { level }
     }
 
-    fn render<const GAME_WIDTH: u8, const GAME_HEIGHT: u8, const PIXEL_WIDTH: u8, R>(
-        &self,
-        point: &Point,
-        target: &mut R,
-    ) where
+    fn render<const PIXEL_WIDTH: u8, R>(&self, point: &Point, sprites: &Sprites, target: &mut R)
+    where
         R: DrawTarget<Color = Rgb565, Error = Error<Infallible>>,
     {
-        _render_point::<PIXEL_WIDTH, R>(point, self.next_colour, target)
+        _render_sprite::<PIXEL_WIDTH, R>(point, sprites.food(self.level), target)
     }
 }
 
+/// The overall state of a `Game`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Running,
+    /// The snake has collided with itself; waiting for a joystick press to restart.
+    GameOver,
+}
+
 pub struct Game<const GAME_WIDTH: u8, const GAME_HEIGHT: u8, const PIXEL_WIDTH: u8>
 where
     [(); GAME_WIDTH as usize * GAME_HEIGHT as usize]:,
 {
     snake: Snake<GAME_WIDTH, GAME_HEIGHT, PIXEL_WIDTH>,
-    inputs: GameInputs,
     food: heapless::FnvIndexMap<Point, Food, 8>,
     /// The number of food items to maintain on the board.
     num_food: usize,
+    rng: Rng,
+    state: State,
+    score: u32,
+    sprites: Sprites,
+    /// The score the HUD was last drawn with, so `_render_hud` can skip redrawing (and thus
+    /// touching any pixels) when the score hasn't changed since the previous render.
+    last_rendered_score: Cell<Option<u32>>,
+    /// Set by `restart` to force the next `render` call to repaint the whole playfield, rather
+    /// than relying on the usual per-cell dirty tracking: the playfield may still be showing the
+    /// previous game's game-over red, which nothing would otherwise touch again.
+    force_repaint: Cell<bool>,
 }
 impl<const GAME_WIDTH: u8, const GAME_HEIGHT: u8, const PIXEL_WIDTH: u8> Game<GAME_WIDTH, GAME_HEIGHT, PIXEL_WIDTH>
 where
     [(); GAME_WIDTH as usize * GAME_HEIGHT as usize]:,
 {
-    pub fn new(inputs: GameInputs) -> Self {
+    /// Above this many failed attempts to find an empty cell for a new food, give up for this
+    /// step rather than spinning forever on a nearly-full board.
+    const MAX_FOOD_PLACEMENT_ATTEMPTS: u32 = 64;
+
+    /// Slow-update period at level 0, in milliseconds (i.e. 2 steps/sec).
+    const BASE_SLOW_UPDATE_PERIOD_MS: u32 = 500;
+    /// How much the period shortens per level.
+    const SLOW_UPDATE_PERIOD_DECREMENT_MS: u32 = 30;
+    /// The period never shortens past this floor, however high the level gets.
+    const MIN_SLOW_UPDATE_PERIOD_MS: u32 = 120;
+    /// How many food items it takes to advance one level.
+    const FOODS_PER_LEVEL: u32 = 3;
+
+    pub fn new(rng_seed: u32) -> Self {
         Game {
             snake: Snake::new(Point::new(GAME_WIDTH / 2, GAME_HEIGHT / 2), Direction::Right),
-            inputs,
             food: heapless::FnvIndexMap::new(),
             num_food: 1,
+            rng: Rng::new(rng_seed),
+            state: State::Running,
+            score: 0,
+            sprites: Sprites::new(),
+            last_rendered_score: Cell::new(None),
+            force_repaint: Cell::new(false),
         }
     }
 
-    /// The "fast" update cycle, for input/non-snake-"step" updates.
-    pub fn fast_update(&mut self) {
-        if let Some(direction) = self.inputs.get_joystick_direction() {
-            // Only change direction if it's not the opposite direction to the current.
-            if Into::<Vector>::into(direction).opposite() != self.snake.get_direction().into() {
-                self.snake.set_direction(direction);
+    /// Reset the snake, food and score back to their initial layout, keeping the RNG as-is, and
+    /// force the next render to repaint the whole playfield (it's likely still showing the
+    /// previous game's game-over red, which this game's own dirty tracking won't repaint on its
+    /// own).
+    fn restart(&mut self) {
+        self.snake = Snake::new(Point::new(GAME_WIDTH / 2, GAME_HEIGHT / 2), Direction::Right);
+        self.food.clear();
+        self.state = State::Running;
+        self.score = 0;
+        self.force_repaint.set(true);
+    }
+
+    /// The current difficulty level, derived from the score: it increases every
+    /// [`Self::FOODS_PER_LEVEL`] food eaten.
+    pub fn level(&self) -> u32 {
+        self.score / Self::FOODS_PER_LEVEL
+    }
+
+    /// The desired period between slow updates at the current level, in milliseconds. Callers
+    /// should re-arm their step timer with this value after every slow update, since it shortens
+    /// as the snake grows.
+    pub fn slow_update_period_ms(&self) -> u32 {
+        let period = Self::BASE_SLOW_UPDATE_PERIOD_MS
+            .saturating_sub(self.level() * Self::SLOW_UPDATE_PERIOD_DECREMENT_MS);
+        period.max(Self::MIN_SLOW_UPDATE_PERIOD_MS)
+    }
+
+    /// Pick a random empty cell to place a new food on, retrying a bounded number of times so we
+    /// never spin forever on a nearly-full board.
+    fn _random_empty_point(&mut self) -> Option<Point> {
+        for _ in 0..Self::MAX_FOOD_PLACEMENT_ATTEMPTS {
+            let x = self.rng.next_u32().rem_euclid(GAME_WIDTH as u32) as u8;
+            let y = self.rng.next_u32().rem_euclid(GAME_HEIGHT as u32) as u8;
+            let point = Point::new(x, y);
+            if !self.snake.contains(&point) && !self.food.contains_key(&point) {
+                return Some(point);
             }
         }
+        None
+    }
+
+    /// The "fast" update cycle, for input/non-snake-"step" updates. `direction` is the latest
+    /// joystick direction latched by the input-sampling task, if any was pressed since the last
+    /// call.
+    pub fn fast_update(&mut self, direction: Option<Direction>) {
+        let Some(direction) = direction else {
+            return;
+        };
+
+        if self.state == State::GameOver {
+            // Any direction press on the game-over screen restarts the game.
+            self.restart();
+            return;
+        }
+
+        // Only change direction if it's not the opposite direction to the current.
+        if Into::<Vector>::into(direction).opposite() != self.snake.get_direction().into() {
+            self.snake.set_direction(direction);
+        }
     }
 
     /// The "slow" update cycle, once every game "step" (snake movement).
     pub fn slow_update(&mut self) {
-        self.snake.update(&mut self.food);
+        if self.state == State::GameOver {
+            return;
+        }
 
-        while self.food.len() < self.num_food {
-            if self.snake.get_head().y < 5 && self.snake.length() < 5 {
-                break;
-            }
-            self.food.insert(Point::new(12, 5), Food::new()).unwrap();
+        let outcome = self.snake.update(&mut self.food);
+        if outcome.collided {
+            self.state = State::GameOver;
+            return;
+        }
+        if outcome.ate_food {
+            self.score += 1;
         }
 
-        for food in self.food.values_mut() {
-            food.update();
+        while self.food.len() < self.num_food {
+            let Some(point) = self._random_empty_point() else {
+                break;
+            };
+            self.food.insert(point, Food::new(self.level())).unwrap();
         }
     }
 
+    /// Render the current frame. The top `PIXEL_WIDTH`-pixel row of the display is reserved for
+    /// the score HUD and is never part of the playfield; `GAME_HEIGHT` covers only the rows below
+    /// it (see the `_playfield_top_left` helper).
     pub fn render<R>(&self, target: &mut R)
     where
         R: DrawTarget<Color = Rgb565, Error = Error<Infallible>>,
     {
+        if self.state == State::GameOver {
+            let size = Size {
+                width: GAME_WIDTH as u32 * PIXEL_WIDTH as u32,
+                height: GAME_HEIGHT as u32 * PIXEL_WIDTH as u32,
+            };
+            let top_left = embedded_graphics::prelude::Point::new(0, PIXEL_WIDTH as i32);
+            target.fill_solid(&Rectangle { top_left, size }, Rgb565::RED).unwrap();
+            self._render_hud(target);
+            return;
+        }
+
+        if self.force_repaint.replace(false) {
+            let size = Size {
+                width: GAME_WIDTH as u32 * PIXEL_WIDTH as u32,
+                height: GAME_HEIGHT as u32 * PIXEL_WIDTH as u32,
+            };
+            let top_left = embedded_graphics::prelude::Point::new(0, PIXEL_WIDTH as i32);
+            target.fill_solid(&Rectangle { top_left, size }, Rgb565::BLACK).unwrap();
+        }
+
         for (point, food) in self.food.iter() {
-            food.render::<GAME_WIDTH, GAME_HEIGHT, PIXEL_WIDTH, R>(point, target);
+            food.render::<PIXEL_WIDTH, R>(point, &self.sprites, target);
+        }
+        self.snake.render(&self.sprites, target);
+        self._render_hud(target);
+    }
+
+    /// Draw the score HUD into its reserved top strip, skipping the draw entirely if the score
+    /// hasn't changed since the last call: besides the obvious saved work, this keeps flushed dirty
+    /// regions tight to wherever the game actually changed instead of pinning them to the HUD's
+    /// corner every single frame.
+    fn _render_hud<R>(&self, target: &mut R)
+    where
+        R: DrawTarget<Color = Rgb565, Error = Error<Infallible>>,
+    {
+        if self.last_rendered_score.get() == Some(self.score) {
+            return;
+        }
+        self.last_rendered_score.set(Some(self.score));
+
+        // Clear the whole strip first: a shorter score (e.g. after a restart) draws fewer glyphs
+        // than before, and without this the old score's trailing digits would never get
+        // overwritten and linger on the panel.
+        let hud_size = Size {
+            width: GAME_WIDTH as u32 * PIXEL_WIDTH as u32,
+            height: PIXEL_WIDTH as u32,
+        };
+        let hud_top_left = embedded_graphics::prelude::Point::new(0, 0);
+        target.fill_solid(&Rectangle { top_left: hud_top_left, size: hud_size }, Rgb565::BLACK).unwrap();
+
+        let mut text: heapless::String<16> = heapless::String::new();
+        let _ = write!(text, "Score: {}", self.score);
+
+        let style = MonoTextStyle::new(&FONT_6X10, Rgb565::WHITE);
+        Text::new(&text, embedded_graphics::prelude::Point::new(2, PIXEL_WIDTH as i32 - 1), style)
+            .draw(target)
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rng_never_produces_zero_from_a_non_zero_seed() {
+        let mut rng = Rng::new(1);
+        for _ in 0..64 {
+            assert_ne!(rng.next_u32(), 0);
+        }
+    }
+
+    #[test]
+    fn rng_treats_a_zero_seed_as_non_zero() {
+        // A zero seed is a fixed point of xorshift: it would otherwise generate nothing but
+        // zeroes forever.
+        let mut rng = Rng::new(0);
+        assert_ne!(rng.next_u32(), 0);
+    }
+
+    #[test]
+    fn rng_does_not_repeat_within_a_short_window() {
+        let mut rng = Rng::new(42);
+        let mut seen: heapless::Vec<u32, 64> = heapless::Vec::new();
+        for _ in 0..64 {
+            let value = rng.next_u32();
+            assert!(!seen.contains(&value), "xorshift32 repeated a value within one period");
+            seen.push(value).unwrap();
         }
-        self.snake.render(target);
+    }
+
+    /// Regression test for the bug fixed in 9987a7f: a snake following its own tail around a
+    /// loop would falsely collide with itself, since the tail cell it's moving into is also the
+    /// one vacating this same step.
+    #[test]
+    fn moving_into_the_vacating_tail_is_not_a_collision() {
+        let mut points = heapless::Deque::new();
+        points.push_back(Point::new(1, 0)).unwrap(); // head
+        points.push_back(Point::new(1, 1)).unwrap();
+        points.push_back(Point::new(0, 1)).unwrap();
+        points.push_back(Point::new(0, 0)).unwrap(); // tail
+        let mut snake: Snake<2, 2, 10> = Snake { points, old_tail: None, direction: Direction::Left };
+
+        let mut food: FnvIndexMap<Point, Food, 1> = FnvIndexMap::new();
+        let outcome = snake.update(&mut food);
+
+        assert!(!outcome.collided);
+        assert!(!outcome.ate_food);
+        assert_eq!(snake.get_head(), Point::new(0, 0));
+    }
+
+    /// A snake that isn't looping still collides with itself normally.
+    #[test]
+    fn moving_into_a_non_vacating_body_cell_is_a_collision() {
+        let mut points = heapless::Deque::new();
+        points.push_back(Point::new(1, 0)).unwrap(); // head
+        points.push_back(Point::new(1, 1)).unwrap();
+        points.push_back(Point::new(0, 1)).unwrap();
+        points.push_back(Point::new(0, 0)).unwrap();
+        points.push_back(Point::new(1, 0)).unwrap(); // re-occupies the head's row, not the tail
+        let mut snake: Snake<2, 2, 10> = Snake { points, old_tail: None, direction: Direction::Left };
+
+        let mut food: FnvIndexMap<Point, Food, 1> = FnvIndexMap::new();
+        let outcome = snake.update(&mut food);
+
+        assert!(outcome.collided);
+    }
+
+    #[test]
+    fn level_increases_every_foods_per_level_points() {
+        let mut game = Game::<4, 4, 10>::new(1);
+        assert_eq!(game.level(), 0);
+
+        game.score = Game::<4, 4, 10>::FOODS_PER_LEVEL;
+        assert_eq!(game.level(), 1);
+
+        game.score = Game::<4, 4, 10>::FOODS_PER_LEVEL * 5;
+        assert_eq!(game.level(), 5);
+    }
+
+    #[test]
+    fn slow_update_period_shortens_with_level_and_floors_out() {
+        let mut game = Game::<4, 4, 10>::new(1);
+        assert_eq!(game.slow_update_period_ms(), Game::<4, 4, 10>::BASE_SLOW_UPDATE_PERIOD_MS);
+
+        game.score = Game::<4, 4, 10>::FOODS_PER_LEVEL;
+        assert_eq!(
+            game.slow_update_period_ms(),
+            Game::<4, 4, 10>::BASE_SLOW_UPDATE_PERIOD_MS - Game::<4, 4, 10>::SLOW_UPDATE_PERIOD_DECREMENT_MS
+        );
+
+        // A very high score would, without the floor, saturate the subtraction into a tiny or
+        // zero period; it must never go below the configured minimum.
+        game.score = 10_000;
+        assert_eq!(game.slow_update_period_ms(), Game::<4, 4, 10>::MIN_SLOW_UPDATE_PERIOD_MS);
     }
 }
@@ -1,5 +1,7 @@
-#![no_std]
-#![no_main]
+// The pure game logic has unit tests that run on the host; everything else here is genuinely
+// target-only, so only opt out of std/the custom entry point when we're not running those.
+#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(test), no_main)]
 #![feature(exhaustive_patterns)]
 #![feature(stmt_expr_attributes)]
 #![feature(mixed_integer_ops)]
@@ -7,20 +9,10 @@
 #![feature(generic_const_exprs)]
 
 //use panic_halt as _; // breakpoint on `rust_begin_unwind` to catch panics
+#[cfg(not(test))]
 use panic_semihosting as _;
 
-use cortex_m_rt::entry;
-use display_interface_spi::SPIInterface;
-use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
-use st7789;
-use stm32f3xx_hal::{
-    block, pac,
-    prelude::*,
-    spi,
-    time::{duration::Milliseconds, rate::Megahertz},
-    timer::Timer,
-};
-
+mod framebuffer;
 mod inputs;
 mod game;
 
@@ -44,86 +36,180 @@ mod game;
 // GPIO22   -> orange -> pd12
 // GPIO23   -> yellow -> pd13
 
-#[entry]
-fn main() -> ! {
-    let core_peripherals = cortex_m::peripheral::Peripherals::take().unwrap();
-    let peripherals = pac::Peripherals::take().unwrap();
-    let mut reset_and_clock_control = peripherals.RCC.constrain();
-    let mut flash = peripherals.FLASH.constrain();
-    let clocks = reset_and_clock_control
-        .cfgr
-        .sysclk(Megahertz(64))
-        .pclk2(Megahertz(64))
-        .freeze(&mut flash.acr);
-    let mut timer = Timer::new(peripherals.TIM1, clocks, &mut reset_and_clock_control.apb2);
-
-    // For determining which bus (ahb) is needed, section 3.2.2 in
-    // https://www.st.com/resource/en/reference_manual/dm00043574-stm32f303xb-c-d-e-stm32f303x6-8-stm32f328x8-stm32f358xc-stm32f398xe-advanced-arm-based-mcus-stmicroelectronics.pdf
-    // documents which peripherals are reachable over which buses.
-    let mut gpioa = peripherals.GPIOA.split(&mut reset_and_clock_control.ahb);
-    let mut gpiod = peripherals.GPIOD.split(&mut reset_and_clock_control.ahb);
-
-    let joystick_up = gpiod.pd10.into_pull_up_input(&mut gpiod.moder, &mut gpiod.pupdr);
-    let joystick_left = gpiod.pd11.into_pull_up_input(&mut gpiod.moder, &mut gpiod.pupdr);
-    let joystick_down = gpiod.pd12.into_pull_up_input(&mut gpiod.moder, &mut gpiod.pupdr);
-    let joystick_right = gpiod.pd13.into_pull_up_input(&mut gpiod.moder, &mut gpiod.pupdr);
-
-    let game_inputs = inputs::GameInputs::new(
-        joystick_up.downgrade().downgrade(),
-        joystick_right.downgrade().downgrade(),
-        joystick_down.downgrade().downgrade(),
-        joystick_left.downgrade().downgrade(),
-    );
+#[rtic::app(device = stm32f3xx_hal::pac, dispatchers = [SPI2])]
+mod app {
+    use display_interface_spi::SPIInterface;
+    use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+    use stm32f3xx_hal::{
+        gpio::{gpioa, Alternate, Output, PushPull},
+        pac,
+        prelude::*,
+        spi,
+        timer::{Event, Timer},
+        time::rate::Megahertz,
+    };
+
+    use crate::{framebuffer, game, inputs};
+
+    const GAME_WIDTH_PIXELS: u8 = 240;
+    const GAME_HEIGHT_PIXELS: u8 = 240;
+    const PIXEL_WIDTH: u8 = 10;
+    const GAME_WIDTH: u8 = GAME_WIDTH_PIXELS / PIXEL_WIDTH;
+    // The top row is reserved for the score HUD, so the playfield gets one fewer row than the
+    // screen could otherwise fit (see `Game::render`'s doc comment).
+    const GAME_HEIGHT: u8 = GAME_HEIGHT_PIXELS / PIXEL_WIDTH - 1;
+
+    const INPUT_SAMPLES_PER_SECOND: u32 = 100;
+    const SLOW_UPDATES_PER_SECOND: u32 = 2;
 
-    let sclk = gpioa
-        .pa5
-        .into_af_push_pull::<5>(&mut gpioa.moder, &mut gpioa.otyper, &mut gpioa.afrl);
-    let miso = gpioa
-        .pa6
-        .into_af_push_pull::<5>(&mut gpioa.moder, &mut gpioa.otyper, &mut gpioa.afrl);
-    let mosi = gpioa
-        .pa7
-        .into_af_push_pull::<5>(&mut gpioa.moder, &mut gpioa.otyper, &mut gpioa.afrl);
-
-    let spi_config = spi::config::Config::default().frequency(Megahertz(20));
-    let spi = spi::Spi::new(
-        peripherals.SPI1,
-        (sclk, miso, mosi),
-        spi_config,
-        clocks,
-        &mut reset_and_clock_control.apb2,
+    type SpiPins = (
+        gpioa::PA5<Alternate<PushPull, 5>>,
+        gpioa::PA6<Alternate<PushPull, 5>>,
+        gpioa::PA7<Alternate<PushPull, 5>>,
     );
+    type Display = st7789::ST7789<
+        SPIInterface<spi::Spi<pac::SPI1, SpiPins>, gpioa::PA2<Output<PushPull>>, gpioa::PA4<Output<PushPull>>>,
+        gpioa::PA3<Output<PushPull>>,
+        gpioa::PA0<Output<PushPull>>,
+    >;
+    type FrameBuffer = framebuffer::FrameBuffer<
+        { GAME_WIDTH_PIXELS as usize },
+        { GAME_HEIGHT_PIXELS as usize },
+        { PIXEL_WIDTH as usize },
+    >;
+
+    /// The latest joystick direction latched by [`sample_input`], consumed (and cleared) by
+    /// [`slow_step`] on the next game step.
+    #[shared]
+    struct Shared {
+        latched_direction: Option<inputs::Direction>,
+    }
 
-    let backlight = gpioa.pa0.into_push_pull_output(&mut gpioa.moder, &mut gpioa.otyper);
-    let data = gpioa.pa2.into_push_pull_output(&mut gpioa.moder, &mut gpioa.otyper);
-    let reset = gpioa.pa3.into_push_pull_output(&mut gpioa.moder, &mut gpioa.otyper);
-    let chip_select = gpioa.pa4.into_push_pull_output(&mut gpioa.moder, &mut gpioa.otyper);
+    #[local]
+    struct Local {
+        game: game::Game<GAME_WIDTH, GAME_HEIGHT, PIXEL_WIDTH>,
+        framebuffer: FrameBuffer,
+        display: Display,
+        game_inputs: inputs::GameInputs,
+        input_timer: Timer<pac::TIM2>,
+        slow_timer: Timer<pac::TIM3>,
+    }
 
-    let spi_interface = SPIInterface::new(spi, data, chip_select);
-    let mut display = st7789::ST7789::new(spi_interface, Some(reset), Some(backlight), 240, 240);
+    #[init]
+    fn init(cx: init::Context) -> (Shared, Local, init::Monotonics) {
+        let core_peripherals = cx.core;
+        let peripherals = cx.device;
+        let mut reset_and_clock_control = peripherals.RCC.constrain();
+        let mut flash = peripherals.FLASH.constrain();
+        let clocks = reset_and_clock_control
+            .cfgr
+            .sysclk(Megahertz(64))
+            .pclk2(Megahertz(64))
+            .freeze(&mut flash.acr);
+
+        // For determining which bus (ahb) is needed, section 3.2.2 in
+        // https://www.st.com/resource/en/reference_manual/dm00043574-stm32f303xb-c-d-e-stm32f303x6-8-stm32f328x8-stm32f358xc-stm32f398xe-advanced-arm-based-mcus-stmicroelectronics.pdf
+        // documents which peripherals are reachable over which buses.
+        let mut gpioa = peripherals.GPIOA.split(&mut reset_and_clock_control.ahb);
+        let mut gpiod = peripherals.GPIOD.split(&mut reset_and_clock_control.ahb);
+
+        let joystick_up = gpiod.pd10.into_pull_up_input(&mut gpiod.moder, &mut gpiod.pupdr);
+        let joystick_left = gpiod.pd11.into_pull_up_input(&mut gpiod.moder, &mut gpiod.pupdr);
+        let joystick_down = gpiod.pd12.into_pull_up_input(&mut gpiod.moder, &mut gpiod.pupdr);
+        let joystick_right = gpiod.pd13.into_pull_up_input(&mut gpiod.moder, &mut gpiod.pupdr);
+
+        let game_inputs = inputs::GameInputs::new(
+            joystick_up.downgrade().downgrade(),
+            joystick_right.downgrade().downgrade(),
+            joystick_down.downgrade().downgrade(),
+            joystick_left.downgrade().downgrade(),
+        );
+
+        let sclk = gpioa
+            .pa5
+            .into_af_push_pull::<5>(&mut gpioa.moder, &mut gpioa.otyper, &mut gpioa.afrl);
+        let miso = gpioa
+            .pa6
+            .into_af_push_pull::<5>(&mut gpioa.moder, &mut gpioa.otyper, &mut gpioa.afrl);
+        let mosi = gpioa
+            .pa7
+            .into_af_push_pull::<5>(&mut gpioa.moder, &mut gpioa.otyper, &mut gpioa.afrl);
+
+        let spi_config = spi::config::Config::default().frequency(Megahertz(20));
+        let spi = spi::Spi::new(
+            peripherals.SPI1,
+            (sclk, miso, mosi),
+            spi_config,
+            clocks,
+            &mut reset_and_clock_control.apb2,
+        );
+
+        let backlight = gpioa.pa0.into_push_pull_output(&mut gpioa.moder, &mut gpioa.otyper);
+        let data = gpioa.pa2.into_push_pull_output(&mut gpioa.moder, &mut gpioa.otyper);
+        let reset = gpioa.pa3.into_push_pull_output(&mut gpioa.moder, &mut gpioa.otyper);
+        let chip_select = gpioa.pa4.into_push_pull_output(&mut gpioa.moder, &mut gpioa.otyper);
+
+        let spi_interface = SPIInterface::new(spi, data, chip_select);
+        let mut display = st7789::ST7789::new(spi_interface, Some(reset), Some(backlight), 240, 240);
+
+        let mut delay = cortex_m::delay::Delay::new(core_peripherals.SYST, clocks.hclk().0);
+        display.init(&mut delay).unwrap();
+        display.clear(Rgb565::BLACK).unwrap();
+
+        // The exact cycle count at startup is as good a source of entropy as we need for picking
+        // food locations, and it's always non-zero by the time we get here.
+        let rng_seed = cortex_m::peripheral::SYST::get_current();
+        let game = game::Game::<GAME_WIDTH, GAME_HEIGHT, PIXEL_WIDTH>::new(rng_seed);
+        let framebuffer = FrameBuffer::new();
+
+        let mut input_timer = Timer::new(peripherals.TIM2, clocks, &mut reset_and_clock_control.apb1);
+        input_timer.enable_interrupt(Event::Update);
+        input_timer.start(stm32f3xx_hal::time::duration::Milliseconds(1000 / INPUT_SAMPLES_PER_SECOND));
+
+        let mut slow_timer = Timer::new(peripherals.TIM3, clocks, &mut reset_and_clock_control.apb1);
+        slow_timer.enable_interrupt(Event::Update);
+        slow_timer.start(stm32f3xx_hal::time::duration::Milliseconds(1000 / SLOW_UPDATES_PER_SECOND));
+
+        (
+            Shared { latched_direction: None },
+            Local { game, framebuffer, display, game_inputs, input_timer, slow_timer },
+            init::Monotonics(),
+        )
+    }
 
-    let mut delay = cortex_m::delay::Delay::new(core_peripherals.SYST, clocks.hclk().0);
-    display.init(&mut delay).unwrap();
-    display.clear(Rgb565::BLACK).unwrap();
+    /// Sample the joystick at a fixed, fast cadence and latch the direction for the next slow
+    /// step to pick up, decoupling input latency from the render cadence. Runs at a higher
+    /// priority than `slow_step` so it can preempt it: `slow_step`'s band-by-band SPI flush is
+    /// long enough that input sampling would otherwise stall for its entire duration.
+    #[task(binds = TIM2, priority = 2, local = [game_inputs, input_timer], shared = [latched_direction])]
+    fn sample_input(mut cx: sample_input::Context) {
+        cx.local.input_timer.clear_interrupt(Event::Update);
 
-    const GAME_WIDTH_PIXELS: u8 = 240;
-    const GAME_HEIGHT_PIXELS: u8 = 240;
-    const PIXEL_WIDTH: u8 = 10;
-    let mut game =
-        game::Game::<{ GAME_WIDTH_PIXELS / PIXEL_WIDTH }, { GAME_HEIGHT_PIXELS / PIXEL_WIDTH }, PIXEL_WIDTH>::new(game_inputs);
+        if let Some(direction) = cx.local.game_inputs.get_joystick_direction() {
+            cx.shared.latched_direction.lock(|latched| *latched = Some(direction));
+        }
+    }
 
-    const SLOW_UPDATES_PER_SECOND: u32 = 2;
-    const FAST_UPDATES_PER_SECOND: u32 = 100;
-    loop {
-        // Render everything and run a single snake move
+    /// Run one snake step, render it, and flush only the changed pixels to the display. Re-arms
+    /// its own timer for the next step, since the period shortens as the snake grows.
+    #[task(binds = TIM3, local = [game, framebuffer, display, slow_timer], shared = [latched_direction])]
+    fn slow_step(mut cx: slow_step::Context) {
+        cx.local.slow_timer.clear_interrupt(Event::Update);
+        let slow_step::LocalResources { game, framebuffer, display, slow_timer, .. } = cx.local;
+
+        let direction = cx.shared.latched_direction.lock(|latched| latched.take());
+        game.fast_update(direction);
         game.slow_update();
-        game.render(&mut display);
 
-        // Then keep fast-updating until we need to do the next game move
-        for _ in 0..(FAST_UPDATES_PER_SECOND / SLOW_UPDATES_PER_SECOND) {
-            timer.start(Milliseconds(1000 / FAST_UPDATES_PER_SECOND));
-            game.fast_update();
-            block!(timer.wait()).unwrap();
+        // The whole scene is re-rendered once per band (see `FrameBuffer`'s docs for why it's
+        // split up this way); `Game::render` is pure, so calling it repeatedly with a different
+        // band selected is just as correct as rendering into a full-screen buffer once.
+        for band in 0..FrameBuffer::BANDS {
+            framebuffer.select_band(band);
+            game.render(framebuffer);
+            framebuffer.flush(display).unwrap();
         }
+
+        slow_timer.start(stm32f3xx_hal::time::duration::Milliseconds(game.slow_update_period_ms()));
     }
 }